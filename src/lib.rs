@@ -1,7 +1,11 @@
 //! Provides helpful worker threads that get signaled to stop when dropped.
 //!
 //! # Features
-//! The `crossbeam` feature will use unbounded [crossbeam](https://crates.io/crates/crossbeam) channels instead of [std](std::sync::mpsc) channels.
+//! The `crossbeam` feature will use [crossbeam](https://crates.io/crates/crossbeam) channels instead of [std](std::sync::mpsc) channels.
+//!
+//! Use [`DropWorker::with_capacity`] (or [`Worker::with_capacity`]) for a bounded input channel that applies backpressure.
+//!
+//! Use [`DropWorker::new_named`] (or [`Worker::new_named`]) to name a worker's thread and log its shutdown via [`log`] — as with any `log` user, this is a no-op until a logger is installed.
 //!
 //! # Example
 //! ```
@@ -34,25 +38,53 @@
 //! ```
 
 #[cfg(feature = "crossbeam")]
-use crossbeam::{unbounded as channel, Sender};
+use crossbeam::{bounded, unbounded as channel, Sender};
 #[cfg(feature = "crossbeam")]
-pub use crossbeam::{Receiver, TryRecvError};
+pub use crossbeam::{Receiver, RecvError, SendError, TryRecvError};
 #[cfg(not(feature = "crossbeam"))]
-use std::sync::mpsc::{channel, Sender};
+use std::sync::mpsc::{channel, sync_channel, Sender as StdSender, SyncSender};
 #[cfg(not(feature = "crossbeam"))]
-pub use std::sync::mpsc::{Receiver, TryRecvError};
+pub use std::sync::mpsc::{Receiver, RecvError, SendError, TryRecvError};
 
 use std::{
-	mem::ManuallyDrop, 
-	ops::Deref, 
-//	thread::JoinHandle
+	mem::ManuallyDrop,
+	ops::Deref,
+	thread::JoinHandle,
 };
 
+/// The sending half of a worker's channel: the input channel of a
+/// [`DropWorker`] or [`Worker`], and the output channel a [`Worker`] hands
+/// to its thread.
+///
+/// The `std` backend uses an unbounded [`Sender`](std::sync::mpsc::Sender)
+/// for [`DropWorker::new`] and a bounded
+/// [`SyncSender`](std::sync::mpsc::SyncSender) for
+/// [`DropWorker::with_capacity`], so this wraps both behind a single type.
+#[cfg(not(feature = "crossbeam"))]
+pub enum Sender<T> {
+    Unbounded(StdSender<T>),
+    Bounded(SyncSender<T>),
+}
+
+#[cfg(not(feature = "crossbeam"))]
+impl<T> Sender<T> {
+    /// Sends data through the channel, blocking if the channel is bounded
+    /// and currently full.
+    pub fn send(&self, data: T) -> Result<(), SendError<T>> {
+        match self {
+            Sender::Unbounded(sender) => sender.send(data),
+            Sender::Bounded(sender) => sender.send(data),
+        }
+    }
+}
+
 /// Provides a worker thread that can receive structs of type `T`.
-/// When this instance is dropped, it will signal the worker thread to stop.
+/// When this instance is dropped, it will signal the worker thread to stop
+/// and block until it has finished.
 pub struct DropWorker<T> {
     sender: ManuallyDrop<Sender<T>>,
-//    thread: JoinHandle<()>,
+    thread: Option<JoinHandle<()>>,
+    name: Option<&'static str>,
 }
 
 /// Checks if the [`DropWorker`] was dropped and if so then this will call
@@ -84,11 +116,57 @@ impl<T: Send + 'static> DropWorker<T> {
     /// The function must accept a [`Receiver`].
     pub fn new<F: Fn(Receiver<T>) + Send + 'static>(func: F) -> Self {
         let (sender, receiver) = channel::<T>();
+        #[cfg(not(feature = "crossbeam"))]
+        let sender = Sender::Unbounded(sender);
+        let sender = ManuallyDrop::new(sender);
+        let thread = std::thread::spawn(move || func(receiver));
+        DropWorker {
+            sender,
+            thread: Some(thread),
+            name: None,
+        }
+    }
+
+    /// Spawns a new, named worker thread with the given function.
+    /// The thread is named via [`thread::Builder`](std::thread::Builder),
+    /// and the name is used to identify this worker in the shutdown logs
+    /// emitted on [`Drop`].
+    pub fn new_named<F: Fn(Receiver<T>) + Send + 'static>(name: &'static str, func: F) -> Self {
+        let (sender, receiver) = channel::<T>();
+        #[cfg(not(feature = "crossbeam"))]
+        let sender = Sender::Unbounded(sender);
         let sender = ManuallyDrop::new(sender);
-        let _thread = std::thread::spawn(move || func(receiver));
-        DropWorker { 
-        	sender, 
-//        	thread 
+        let thread = std::thread::Builder::new()
+            .name(name.to_owned())
+            .spawn(move || func(receiver))
+            .expect("failed to spawn worker thread");
+        DropWorker {
+            sender,
+            thread: Some(thread),
+            name: Some(name),
+        }
+    }
+
+    /// Spawns a new worker thread with the given function, using a bounded
+    /// channel of size `buf` for its input.
+    ///
+    /// This gives backpressure: once the worker falls behind, `send` blocks
+    /// until there is room in the channel. A `buf` of `0` creates a
+    /// rendezvous channel, where `send` blocks until the worker is ready to
+    /// receive.
+    pub fn with_capacity<F: Fn(Receiver<T>) + Send + 'static>(buf: usize, func: F) -> Self {
+        #[cfg(feature = "crossbeam")]
+        let (sender, receiver) = bounded::<T>(buf);
+        #[cfg(not(feature = "crossbeam"))]
+        let (sender, receiver) = sync_channel::<T>(buf);
+        #[cfg(not(feature = "crossbeam"))]
+        let sender = Sender::Bounded(sender);
+        let sender = ManuallyDrop::new(sender);
+        let thread = std::thread::spawn(move || func(receiver));
+        DropWorker {
+            sender,
+            thread: Some(thread),
+            name: None,
         }
     }
 }
@@ -101,13 +179,199 @@ impl<T: Send + 'static> Deref for DropWorker<T> {
     }
 }
 
+impl<T: Send + 'static> DropWorker<T> {
+    /// Signals the worker to stop and blocks until it finishes, returning
+    /// its join result instead of resuming a worker panic on the caller's
+    /// thread as [`Drop`] does. This also lets callers choose when the
+    /// (potentially blocking) join happens, instead of having it happen
+    /// implicitly when the value goes out of scope.
+    pub fn shutdown(self) -> std::thread::Result<()> {
+        let mut this = ManuallyDrop::new(self);
+        this.shutdown_impl()
+    }
+}
+
+impl<T> DropWorker<T> {
+    fn shutdown_impl(&mut self) -> std::thread::Result<()> {
+        unsafe {
+            ManuallyDrop::drop(&mut self.sender);
+        }
+        let thread = match self.thread.take() {
+            Some(thread) => thread,
+            None => return Ok(()),
+        };
+        if let Some(name) = self.name {
+            log::info!("waiting for {} to finish", name);
+        }
+        let result = thread.join();
+        if let Some(name) = self.name {
+            match &result {
+                Ok(()) => log::info!("{} finished", name),
+                Err(_) => log::error!("{} finished with an error", name),
+            }
+        }
+        result
+    }
+}
+
 impl<T> Drop for DropWorker<T> {
     fn drop(&mut self) {
-        // let thread;
+        if let Err(payload) = self.shutdown_impl() {
+            if !std::thread::panicking() {
+                std::panic::resume_unwind(payload);
+            }
+        }
+    }
+}
+
+/// Provides a bidirectional worker thread that can receive structs of type
+/// `I` and send back structs of type `O`.
+/// When this instance is dropped, it will signal the worker thread to stop
+/// and block until it has finished.
+pub struct Worker<I, O> {
+    sender: ManuallyDrop<Sender<I>>,
+    receiver: Receiver<O>,
+    thread: Option<JoinHandle<()>>,
+    name: Option<&'static str>,
+}
+
+impl<I: Send + 'static, O: Send + 'static> Worker<I, O> {
+    /// Spawns a new worker thread with the given function.
+    /// The function must accept a [`Receiver`] for its input and a
+    /// [`Sender`] to send its results back.
+    pub fn new<F: Fn(Receiver<I>, Sender<O>) + Send + 'static>(func: F) -> Self {
+        let (in_sender, in_receiver) = channel::<I>();
+        let (out_sender, out_receiver) = channel::<O>();
+        #[cfg(not(feature = "crossbeam"))]
+        let in_sender = Sender::Unbounded(in_sender);
+        #[cfg(not(feature = "crossbeam"))]
+        let out_sender = Sender::Unbounded(out_sender);
+        let in_sender = ManuallyDrop::new(in_sender);
+        let thread = std::thread::spawn(move || func(in_receiver, out_sender));
+        Worker {
+            sender: in_sender,
+            receiver: out_receiver,
+            thread: Some(thread),
+            name: None,
+        }
+    }
+
+    /// Spawns a new, named worker thread with the given function.
+    /// The thread is named via [`thread::Builder`](std::thread::Builder),
+    /// and the name is used to identify this worker in the shutdown logs
+    /// emitted on [`Drop`].
+    pub fn new_named<F: Fn(Receiver<I>, Sender<O>) + Send + 'static>(
+        name: &'static str,
+        func: F,
+    ) -> Self {
+        let (in_sender, in_receiver) = channel::<I>();
+        let (out_sender, out_receiver) = channel::<O>();
+        #[cfg(not(feature = "crossbeam"))]
+        let in_sender = Sender::Unbounded(in_sender);
+        #[cfg(not(feature = "crossbeam"))]
+        let out_sender = Sender::Unbounded(out_sender);
+        let in_sender = ManuallyDrop::new(in_sender);
+        let thread = std::thread::Builder::new()
+            .name(name.to_owned())
+            .spawn(move || func(in_receiver, out_sender))
+            .expect("failed to spawn worker thread");
+        Worker {
+            sender: in_sender,
+            receiver: out_receiver,
+            thread: Some(thread),
+            name: Some(name),
+        }
+    }
+
+    /// Spawns a new worker thread with the given function, using a bounded
+    /// channel of size `buf` for its input.
+    ///
+    /// Only the input side is bounded; the output channel stays unbounded.
+    /// Bounding both sides would let the worker block on sending a result
+    /// while the caller is blocked sending new input, deadlocking both ends.
+    pub fn with_capacity<F: Fn(Receiver<I>, Sender<O>) + Send + 'static>(
+        buf: usize,
+        func: F,
+    ) -> Self {
+        #[cfg(feature = "crossbeam")]
+        let (in_sender, in_receiver) = bounded::<I>(buf);
+        #[cfg(not(feature = "crossbeam"))]
+        let (in_sender, in_receiver) = sync_channel::<I>(buf);
+        #[cfg(not(feature = "crossbeam"))]
+        let in_sender = Sender::Bounded(in_sender);
+        let (out_sender, out_receiver) = channel::<O>();
+        #[cfg(not(feature = "crossbeam"))]
+        let out_sender = Sender::Unbounded(out_sender);
+        let in_sender = ManuallyDrop::new(in_sender);
+        let thread = std::thread::spawn(move || func(in_receiver, out_sender));
+        Worker {
+            sender: in_sender,
+            receiver: out_receiver,
+            thread: Some(thread),
+            name: None,
+        }
+    }
+
+    /// Sends data to the worker thread.
+    pub fn send(&self, data: I) -> Result<(), SendError<I>> {
+        self.sender.send(data)
+    }
+
+    /// Waits for a result from the worker thread.
+    pub fn recv(&self) -> Result<O, RecvError> {
+        self.receiver.recv()
+    }
+
+    /// Signals the worker to stop and blocks until it finishes, returning
+    /// its join result instead of resuming a worker panic on the caller's
+    /// thread as [`Drop`] does. This also lets callers choose when the
+    /// (potentially blocking) join happens, instead of having it happen
+    /// implicitly when the value goes out of scope.
+    pub fn shutdown(self) -> std::thread::Result<()> {
+        let mut this = ManuallyDrop::new(self);
+        let result = this.shutdown_impl();
+        // `shutdown_impl` only handles `sender` and `thread`; drop the
+        // output receiver ourselves since wrapping `self` in `ManuallyDrop`
+        // suppresses its normal field drop glue.
+        unsafe {
+            std::ptr::drop_in_place(&mut this.receiver);
+        }
+        result
+    }
+}
+
+impl<I, O> Worker<I, O> {
+    fn shutdown_impl(&mut self) -> std::thread::Result<()> {
         unsafe {
             ManuallyDrop::drop(&mut self.sender);
-            // thread = std::mem::replace(&mut self.thread, std::thread::spawn(|| ()));
         }
-        // let _ = thread.join();
+        let thread = match self.thread.take() {
+            Some(thread) => thread,
+            None => return Ok(()),
+        };
+        if let Some(name) = self.name {
+            log::info!("waiting for {} to finish", name);
+        }
+        let result = thread.join();
+        if let Some(name) = self.name {
+            match &result {
+                Ok(()) => log::info!("{} finished", name),
+                Err(_) => log::error!("{} finished with an error", name),
+            }
+        }
+        // `self.receiver` is dropped after the join, so the worker can
+        // safely send its final results without panicking on a closed
+        // channel while it is shutting down.
+        result
+    }
+}
+
+impl<I, O> Drop for Worker<I, O> {
+    fn drop(&mut self) {
+        if let Err(payload) = self.shutdown_impl() {
+            if !std::thread::panicking() {
+                std::panic::resume_unwind(payload);
+            }
+        }
     }
 }